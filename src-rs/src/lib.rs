@@ -0,0 +1,6 @@
+//! A pure Rust implementation of PlanetSide 2's SOE network protocol.
+
+pub mod services;
+pub mod session;
+pub mod soe_op_code;
+pub mod util;
@@ -0,0 +1,155 @@
+use std::time::{Duration, Instant};
+
+/// Default number of in-order reliable packets to receive before sending a
+/// cumulative `Acknowledge`.
+pub const DEFAULT_ACK_THRESHOLD: u16 = 2;
+
+/// Default maximum delay before an `Acknowledge` is sent even if `ack_threshold`
+/// has not been reached.
+pub const DEFAULT_MAX_ACK_DELAY: Duration = Duration::from_millis(25);
+
+/// Session-config parameters governing how aggressively `Acknowledge` packets
+/// for received reliable data are coalesced.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct AckPolicyConfig {
+    /// The number of in-order reliable packets to receive before an `Acknowledge`
+    /// is sent, absent an earlier `max_ack_delay` timeout.
+    pub ack_threshold: u16,
+    /// The maximum time to hold back an `Acknowledge` once data is pending.
+    pub max_ack_delay: Duration
+}
+
+impl AckPolicyConfig {
+    /// Negotiates the effective parameters between this session's configured
+    /// preferences and the peer's advertised preferences, taking the more
+    /// demanding (smaller) value of each so neither side sees acks arrive less
+    /// often than it asked for.
+    pub fn negotiate(&self, peer: &AckPolicyConfig) -> AckPolicyConfig {
+        AckPolicyConfig {
+            ack_threshold: self.ack_threshold.min(peer.ack_threshold),
+            max_ack_delay: self.max_ack_delay.min(peer.max_ack_delay)
+        }
+    }
+}
+
+impl Default for AckPolicyConfig {
+    fn default() -> Self {
+        AckPolicyConfig {
+            ack_threshold: DEFAULT_ACK_THRESHOLD,
+            max_ack_delay: DEFAULT_MAX_ACK_DELAY
+        }
+    }
+}
+
+/// Decides when a received reliable sequence should produce an `Acknowledge`,
+/// coalescing several in-order arrivals into a single cumulative ack rather than
+/// sending one per packet. An `OutOfOrder` condition (a gap detected via
+/// [`crate::util::data_utils::get_true_incoming_sequence`]) always bypasses the
+/// delay so the peer can fast-retransmit.
+pub struct AckPolicy {
+    config: AckPolicyConfig,
+    pending_since: Option<Instant>,
+    pending_count: u16
+}
+
+impl AckPolicy {
+    /// Creates a policy with the given negotiated configuration.
+    pub fn new(config: AckPolicyConfig) -> Self {
+        AckPolicy {
+            config,
+            pending_since: None,
+            pending_count: 0
+        }
+    }
+
+    /// Gets the effective ack-coalescing configuration this policy was created
+    /// with.
+    pub fn config(&self) -> AckPolicyConfig {
+        self.config
+    }
+
+    /// Records that an in-order reliable sequence has arrived at `now`. Returns
+    /// `true` if an `Acknowledge` should be sent immediately, because
+    /// `ack_threshold` has now been reached.
+    pub fn on_in_order_packet(&mut self, now: Instant) -> bool {
+        self.pending_count += 1;
+        self.pending_since.get_or_insert(now);
+
+        if self.pending_count >= self.config.ack_threshold {
+            self.on_ack_sent();
+            return true;
+        }
+
+        false
+    }
+
+    /// Records that a reliable sequence arrived out of order. Always forces an
+    /// immediate `Acknowledge`.
+    pub fn on_out_of_order_packet(&mut self) {
+        self.on_ack_sent();
+    }
+
+    /// Returns `true` if `max_ack_delay` has elapsed since data first became
+    /// pending, i.e. the delayed-ack timer has fired.
+    pub fn is_delay_expired(&self, now: Instant) -> bool {
+        match self.pending_since {
+            Some(since) => now.duration_since(since) >= self.config.max_ack_delay,
+            None => false
+        }
+    }
+
+    /// Clears pending-ack state after an `Acknowledge` has been sent, whether
+    /// because the threshold was reached, the delay timer fired, or an
+    /// out-of-order packet forced it.
+    pub fn on_ack_sent(&mut self) {
+        self.pending_since = None;
+        self.pending_count = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn acks_once_the_threshold_is_reached() {
+        let mut policy = AckPolicy::new(AckPolicyConfig { ack_threshold: 2, max_ack_delay: Duration::from_millis(25) });
+        let now = Instant::now();
+
+        assert!(!policy.on_in_order_packet(now));
+        assert!(policy.on_in_order_packet(now));
+    }
+
+    #[test]
+    fn delay_timer_expires_before_threshold_is_reached() {
+        let mut policy = AckPolicy::new(AckPolicyConfig { ack_threshold: 10, max_ack_delay: Duration::from_millis(25) });
+        let now = Instant::now();
+
+        policy.on_in_order_packet(now);
+
+        assert!(!policy.is_delay_expired(now));
+        assert!(policy.is_delay_expired(now + Duration::from_millis(30)));
+    }
+
+    #[test]
+    fn out_of_order_packet_clears_pending_state_immediately() {
+        let mut policy = AckPolicy::new(AckPolicyConfig::default());
+        let now = Instant::now();
+        policy.on_in_order_packet(now);
+
+        policy.on_out_of_order_packet();
+
+        assert!(!policy.is_delay_expired(now + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn negotiation_takes_the_more_demanding_value_of_each_field() {
+        let local = AckPolicyConfig { ack_threshold: 2, max_ack_delay: Duration::from_millis(25) };
+        let peer = AckPolicyConfig { ack_threshold: 4, max_ack_delay: Duration::from_millis(10) };
+
+        let negotiated = local.negotiate(&peer);
+
+        assert_eq!(negotiated.ack_threshold, 2);
+        assert_eq!(negotiated.max_ack_delay, Duration::from_millis(10));
+    }
+}
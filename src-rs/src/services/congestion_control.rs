@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+/// The maximum segment size assumed for congestion window accounting, in bytes.
+pub const MAX_SEGMENT_SIZE: usize = 512;
+
+/// A pluggable congestion control algorithm governing how many bytes of reliable
+/// data a session may have in flight at any one time.
+///
+/// Implementations are driven by the reliable-data send/ack/loss lifecycle and
+/// are consulted via [`can_send`](CongestionControl::can_send) before the next
+/// unacked sequence is pumped onto the wire.
+pub trait CongestionControl {
+    /// Called immediately after `bytes` worth of reliable data has been sent for
+    /// `sequence`.
+    fn on_packet_sent(&mut self, sequence: u64, bytes: usize);
+
+    /// Called when an `Acknowledge` confirms `bytes` worth of previously in-flight
+    /// data for `sequence`, along with the round-trip time sample observed for it.
+    fn on_ack(&mut self, sequence: u64, bytes: usize, rtt: Duration);
+
+    /// Called when `lost_sequence` is declared lost, either via a retransmission
+    /// timeout or reordering-based detection.
+    fn on_congestion_event(&mut self, lost_sequence: u64);
+
+    /// Gets the number of bytes that may currently be sent without exceeding the
+    /// congestion window.
+    fn can_send(&self) -> usize;
+
+    /// Gets the current congestion window, in bytes.
+    fn congestion_window(&self) -> usize;
+}
+
+/// Selects which [`CongestionControl`] algorithm a session should drive its
+/// reliable-data send rate with.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum CongestionControlAlgorithm {
+    /// The standard NewReno algorithm. See [`crate::services::new_reno::NewReno`].
+    #[default]
+    NewReno,
+    /// The CUBIC algorithm. See [`crate::services::cubic::Cubic`].
+    Cubic
+}
+
+impl CongestionControlAlgorithm {
+    /// Constructs the [`CongestionControl`] implementation for this algorithm.
+    pub fn build(self) -> Box<dyn CongestionControl> {
+        match self {
+            CongestionControlAlgorithm::NewReno => Box::new(crate::services::new_reno::NewReno::new()),
+            CongestionControlAlgorithm::Cubic => Box::new(crate::services::cubic::Cubic::new())
+        }
+    }
+}
+
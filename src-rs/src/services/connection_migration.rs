@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+
+/// Length, in bytes, of the random token exchanged while validating a
+/// connection migration.
+pub const MIGRATION_TOKEN_LENGTH: usize = 16;
+
+/// The outcome of observing an inbound packet against a session's migration
+/// state.
+#[derive(Debug, Eq, PartialEq)]
+pub enum MigrationAction {
+    /// The packet came from the session's current, already-validated address, or
+    /// from the candidate address whose challenge is already outstanding; no
+    /// action is required.
+    None,
+    /// The packet came from a new address while no migration was in progress. A
+    /// `RemapConnection` challenge carrying `token` should be sent to `candidate`
+    /// before the session is rebound.
+    ChallengeCandidate { candidate: SocketAddr, token: [u8; MIGRATION_TOKEN_LENGTH] },
+    /// The packet came from a third address while a migration to a different
+    /// candidate was already in progress; ignored so an off-path attacker cannot
+    /// interrupt a validation already underway.
+    IgnoredDuringValidation
+}
+
+/// Per-session connection-migration state.
+///
+/// Modelled on QUIC connection migration: when a validated session's packets
+/// start arriving from a new `SocketAddr`, the session is only rebound to it
+/// after a challenge-response round (a `RemapConnection` carrying a random
+/// token, echoed back by the peer) proves the new address can see traffic sent
+/// to it, preventing an off-path attacker from redirecting the session with a
+/// spoofed `RemapConnection`.
+pub struct ConnectionMigration {
+    current: SocketAddr,
+    pending: Option<(SocketAddr, [u8; MIGRATION_TOKEN_LENGTH])>
+}
+
+impl ConnectionMigration {
+    /// Creates migration state for a session whose validated address is `current`.
+    pub fn new(current: SocketAddr) -> Self {
+        ConnectionMigration { current, pending: None }
+    }
+
+    /// Gets the currently validated remote address.
+    pub fn current(&self) -> SocketAddr {
+        self.current
+    }
+
+    /// Observes an inbound packet from `from`, generating a token via
+    /// `next_token` if a new migration challenge needs to be issued.
+    pub fn on_packet_received(
+        &mut self,
+        from: SocketAddr,
+        next_token: impl FnOnce() -> [u8; MIGRATION_TOKEN_LENGTH]
+    ) -> MigrationAction {
+        if from == self.current {
+            return MigrationAction::None;
+        }
+
+        match self.pending {
+            Some((candidate, _)) if candidate == from => MigrationAction::None,
+            Some(_) => MigrationAction::IgnoredDuringValidation,
+            None => {
+                let token = next_token();
+                self.pending = Some((from, token));
+                MigrationAction::ChallengeCandidate { candidate: from, token }
+            }
+        }
+    }
+
+    /// Validates an echoed `RemapConnection` response from `from`. If it matches
+    /// the outstanding challenge, the session is rebound to `from` and `true` is
+    /// returned; otherwise the migration state is left untouched and `false` is
+    /// returned.
+    pub fn on_remap_response(&mut self, from: SocketAddr, echoed_token: &[u8]) -> bool {
+        let Some((candidate, token)) = self.pending else {
+            return false;
+        };
+
+        if candidate != from || token.as_slice() != echoed_token {
+            return false;
+        }
+
+        self.current = candidate;
+        self.pending = None;
+        true
+    }
+}
+
+/// Maps session ids to the peer address currently believed for each, so an
+/// inbound packet can be routed to the right session, and an `UnknownSender`
+/// reply can be generated for a packet from an address with no mapping.
+///
+/// There is no node/endpoint type in this crate yet that owns one of these
+/// per listening socket and consults it for inbound routing or calls
+/// `rebind` once a [`ConnectionMigration`] completes; nothing outside this
+/// module's own tests constructs a `SessionAddressTable` today.
+#[derive(Default)]
+pub struct SessionAddressTable {
+    by_session_id: HashMap<u32, SocketAddr>,
+    by_address: HashMap<SocketAddr, u32>
+}
+
+impl SessionAddressTable {
+    /// Creates an empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `session_id` as reachable at `address`.
+    pub fn insert(&mut self, session_id: u32, address: SocketAddr) {
+        if let Some(previous) = self.by_session_id.insert(session_id, address) {
+            self.by_address.remove(&previous);
+        }
+
+        // `address` may already be owned by a different session (e.g. it just
+        // migrated away from it); that session's reverse mapping must be
+        // cleared too, or the two maps diverge and an unrelated `remove` later
+        // evicts this address out from under the session we just bound it to.
+        if let Some(previous_owner) = self.by_address.insert(address, session_id) {
+            if previous_owner != session_id {
+                self.by_session_id.remove(&previous_owner);
+            }
+        }
+    }
+
+    /// Removes `session_id` and its address mapping.
+    pub fn remove(&mut self, session_id: u32) {
+        if let Some(address) = self.by_session_id.remove(&session_id) {
+            self.by_address.remove(&address);
+        }
+    }
+
+    /// Looks up the session id a packet from `address` belongs to. `None`
+    /// indicates the packet should be met with an `UnknownSender` reply.
+    pub fn session_for_address(&self, address: &SocketAddr) -> Option<u32> {
+        self.by_address.get(address).copied()
+    }
+
+    /// Rebinds `session_id` to `new_address`, as the final step of a validated
+    /// [`ConnectionMigration`].
+    pub fn rebind(&mut self, session_id: u32, new_address: SocketAddr) {
+        self.insert(session_id, new_address);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(port: u16) -> SocketAddr {
+        format!("127.0.0.1:{port}").parse().unwrap()
+    }
+
+    #[test]
+    fn packets_from_the_current_address_require_no_action() {
+        let mut migration = ConnectionMigration::new(addr(1000));
+
+        let action = migration.on_packet_received(addr(1000), || [0; MIGRATION_TOKEN_LENGTH]);
+
+        assert_eq!(action, MigrationAction::None);
+    }
+
+    #[test]
+    fn a_new_address_is_challenged_before_being_trusted() {
+        let mut migration = ConnectionMigration::new(addr(1000));
+        let token = [7; MIGRATION_TOKEN_LENGTH];
+
+        let action = migration.on_packet_received(addr(2000), || token);
+
+        assert_eq!(action, MigrationAction::ChallengeCandidate { candidate: addr(2000), token });
+        assert_eq!(migration.current(), addr(1000));
+    }
+
+    #[test]
+    fn echoing_the_correct_token_completes_the_migration() {
+        let mut migration = ConnectionMigration::new(addr(1000));
+        let token = [7; MIGRATION_TOKEN_LENGTH];
+        migration.on_packet_received(addr(2000), || token);
+
+        let migrated = migration.on_remap_response(addr(2000), &token);
+
+        assert!(migrated);
+        assert_eq!(migration.current(), addr(2000));
+    }
+
+    #[test]
+    fn an_incorrect_echoed_token_does_not_rebind_the_session() {
+        let mut migration = ConnectionMigration::new(addr(1000));
+        migration.on_packet_received(addr(2000), || [7; MIGRATION_TOKEN_LENGTH]);
+
+        let migrated = migration.on_remap_response(addr(2000), &[0; MIGRATION_TOKEN_LENGTH]);
+
+        assert!(!migrated);
+        assert_eq!(migration.current(), addr(1000));
+    }
+
+    #[test]
+    fn a_third_address_is_ignored_while_a_validation_is_in_flight() {
+        let mut migration = ConnectionMigration::new(addr(1000));
+        migration.on_packet_received(addr(2000), || [7; MIGRATION_TOKEN_LENGTH]);
+
+        let action = migration.on_packet_received(addr(3000), || [9; MIGRATION_TOKEN_LENGTH]);
+
+        assert_eq!(action, MigrationAction::IgnoredDuringValidation);
+    }
+
+    #[test]
+    fn table_reports_unknown_sender_for_unregistered_addresses() {
+        let mut table = SessionAddressTable::new();
+        table.insert(1, addr(1000));
+
+        assert_eq!(table.session_for_address(&addr(1000)), Some(1));
+        assert_eq!(table.session_for_address(&addr(2000)), None);
+    }
+
+    #[test]
+    fn rebinding_moves_the_address_mapping_to_the_new_address() {
+        let mut table = SessionAddressTable::new();
+        table.insert(1, addr(1000));
+
+        table.rebind(1, addr(2000));
+
+        assert_eq!(table.session_for_address(&addr(1000)), None);
+        assert_eq!(table.session_for_address(&addr(2000)), Some(1));
+    }
+
+    #[test]
+    fn rebinding_onto_another_sessions_address_clears_that_sessions_mapping() {
+        let mut table = SessionAddressTable::new();
+        table.insert(1, addr(1000));
+        table.insert(2, addr(2000));
+
+        table.rebind(1, addr(2000));
+        assert_eq!(table.session_for_address(&addr(2000)), Some(1));
+
+        // Session 2 no longer owns any address, so tearing it down must not
+        // touch session 1's freshly rebound mapping.
+        table.remove(2);
+
+        assert_eq!(table.session_for_address(&addr(2000)), Some(1));
+    }
+}
@@ -0,0 +1,206 @@
+use std::time::{Duration, Instant};
+
+use crate::services::congestion_control::{CongestionControl, MAX_SEGMENT_SIZE};
+use crate::services::hystart::{HyStart, SlowStartMode, CSS_GROWTH_DIVISOR};
+
+/// Window reduction factor applied on a congestion event.
+const BETA: f64 = 0.7;
+
+/// Window growth-rate constant.
+const CUBIC_C: f64 = 0.4;
+
+/// A [`CongestionControl`] implementation of CUBIC (RFC 8312), better suited than
+/// NewReno to high-bandwidth, high-latency sessions since its window growth is a
+/// function of elapsed time rather than one-segment-per-RTT. Slow start is gated
+/// by [`HyStart`], same as [`crate::services::new_reno::NewReno`].
+pub struct Cubic {
+    cwnd: f64,
+    ssthresh: usize,
+    /// The congestion window, in segments, measured just before the last reduction.
+    w_max: f64,
+    /// The time at which `cwnd` should reach `w_max` again, relative to `reduced_at`.
+    k: f64,
+    reduced_at: Option<Instant>,
+    bytes_in_flight: usize,
+    srtt: Duration,
+    hystart: HyStart
+}
+
+impl Cubic {
+    /// Creates a new controller, starting in slow start with an unbounded `ssthresh`.
+    pub fn new() -> Self {
+        Cubic {
+            cwnd: (2 * MAX_SEGMENT_SIZE) as f64,
+            ssthresh: usize::MAX,
+            w_max: 0.0,
+            k: 0.0,
+            reduced_at: None,
+            bytes_in_flight: 0,
+            srtt: Duration::from_millis(100),
+            hystart: HyStart::new()
+        }
+    }
+
+    /// Returns `true` while the controller is still in the slow-start phase.
+    pub fn in_slow_start(&self) -> bool {
+        (self.cwnd as usize) < self.ssthresh
+    }
+
+    /// The CUBIC window function `W(t)`, in bytes.
+    fn w_cubic(&self, t: f64) -> f64 {
+        let segments = CUBIC_C * (t - self.k).powi(3) + self.w_max;
+        segments * MAX_SEGMENT_SIZE as f64
+    }
+
+    /// The TCP-Reno-friendly window estimate, in bytes, so CUBIC never underperforms
+    /// Reno on short RTTs.
+    fn w_est(&self, t: f64) -> f64 {
+        let rtt_secs = self.srtt.as_secs_f64().max(0.001);
+        let segments = self.w_max * BETA
+            + 3.0 * (1.0 - BETA) / (1.0 + BETA) * (t / rtt_secs);
+        segments * MAX_SEGMENT_SIZE as f64
+    }
+
+    /// Enters (or re-enters) congestion avoidance at `cwnd`, seeding `w_max`/`k`
+    /// so `w_cubic`/`w_est` are sane immediately - whether this is reached via a
+    /// congestion event or a HyStart-triggered slow-start exit, where no loss
+    /// has occurred to derive them from.
+    fn enter_congestion_avoidance(&mut self, cwnd: f64) {
+        self.ssthresh = cwnd as usize;
+        self.cwnd = cwnd;
+        self.w_max = cwnd / MAX_SEGMENT_SIZE as f64;
+        self.k = 0.0;
+        self.reduced_at = Some(Instant::now());
+        self.hystart.reset();
+    }
+}
+
+impl Default for Cubic {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CongestionControl for Cubic {
+    fn on_packet_sent(&mut self, sequence: u64, bytes: usize) {
+        self.bytes_in_flight += bytes;
+
+        if self.in_slow_start() {
+            self.hystart.on_packet_sent(sequence);
+        }
+    }
+
+    fn on_ack(&mut self, sequence: u64, bytes: usize, rtt: Duration) {
+        self.bytes_in_flight = self.bytes_in_flight.saturating_sub(bytes);
+        self.srtt = rtt;
+
+        if !self.in_slow_start() {
+            let reduced_at = *self.reduced_at.get_or_insert_with(Instant::now);
+            let t = reduced_at.elapsed().as_secs_f64();
+
+            let target = self.w_cubic(t + rtt.as_secs_f64()).max(self.w_est(t));
+            self.cwnd += ((target - self.cwnd) / self.cwnd) * MAX_SEGMENT_SIZE as f64;
+            return;
+        }
+
+        match self.hystart.on_ack(sequence, rtt) {
+            SlowStartMode::SlowStart => self.cwnd += MAX_SEGMENT_SIZE as f64,
+            SlowStartMode::ConservativeSlowStart => {
+                self.cwnd += MAX_SEGMENT_SIZE as f64 / CSS_GROWTH_DIVISOR as f64;
+            }
+            SlowStartMode::ExitSlowStart => self.enter_congestion_avoidance(self.cwnd)
+        }
+    }
+
+    fn on_congestion_event(&mut self, _lost_sequence: u64) {
+        let w_max = self.cwnd / MAX_SEGMENT_SIZE as f64;
+        let cwnd = (self.cwnd * BETA).max(2.0 * MAX_SEGMENT_SIZE as f64);
+
+        self.enter_congestion_avoidance(cwnd);
+
+        // A real congestion event derives `w_max`/`k` from the window measured
+        // just before the reduction, overriding the defaults `enter_congestion_avoidance`
+        // seeds for a loss-free (HyStart) entry.
+        self.w_max = w_max;
+        self.k = (w_max * (1.0 - BETA) / CUBIC_C).cbrt();
+    }
+
+    fn can_send(&self) -> usize {
+        (self.cwnd as usize).saturating_sub(self.bytes_in_flight)
+    }
+
+    fn congestion_window(&self) -> usize {
+        self.cwnd as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slow_start_grows_by_one_segment_per_ack() {
+        let mut cubic = Cubic::new();
+        let initial = cubic.congestion_window();
+
+        cubic.on_ack(1, MAX_SEGMENT_SIZE, Duration::from_millis(50));
+
+        assert_eq!(cubic.congestion_window(), initial + MAX_SEGMENT_SIZE);
+        assert!(cubic.in_slow_start());
+    }
+
+    #[test]
+    fn congestion_event_applies_beta_and_exits_slow_start() {
+        let mut cubic = Cubic::new();
+        for seq in 1..=10u64 {
+            cubic.on_ack(seq, MAX_SEGMENT_SIZE, Duration::from_millis(50));
+        }
+        let cwnd_before = cubic.congestion_window() as f64;
+
+        cubic.on_congestion_event(1);
+
+        assert_eq!(
+            cubic.congestion_window(),
+            ((cwnd_before * BETA).max(2.0 * MAX_SEGMENT_SIZE as f64)) as usize
+        );
+        assert!(!cubic.in_slow_start());
+    }
+
+    #[test]
+    fn hystart_triggered_exit_does_not_oscillate_congestion_avoidance() {
+        let mut cubic = Cubic::new();
+
+        // Six rounds: a flat baseline, then five rounds of consistently elevated
+        // RTT, which drives HyStart++ through Conservative Slow Start and into
+        // `ExitSlowStart` on the last round's closing ack - with no loss involved.
+        let rounds = [(1, 8, 20), (9, 16, 60), (17, 24, 60), (25, 32, 60), (33, 40, 60), (41, 48, 60)];
+        for (start, end, rtt_ms) in rounds {
+            cubic.on_packet_sent(end, MAX_SEGMENT_SIZE);
+            for seq in start..=end {
+                cubic.on_ack(seq, MAX_SEGMENT_SIZE, Duration::from_millis(rtt_ms));
+            }
+        }
+        assert!(!cubic.in_slow_start());
+        let cwnd_at_exit = cubic.congestion_window() as f64;
+
+        cubic.on_ack(49, MAX_SEGMENT_SIZE, Duration::from_millis(60));
+
+        // Without seeding `w_max`/`k` on a loss-free exit, the next ack's target
+        // collapses towards zero and `cwnd` drops by roughly a full segment.
+        assert!(cwnd_at_exit - (cubic.congestion_window() as f64) < MAX_SEGMENT_SIZE as f64 / 2.0);
+    }
+
+    #[test]
+    fn congestion_avoidance_never_underperforms_reno_estimate() {
+        let mut cubic = Cubic::new();
+        for seq in 1..=10u64 {
+            cubic.on_ack(seq, MAX_SEGMENT_SIZE, Duration::from_millis(20));
+        }
+        cubic.on_congestion_event(1);
+        let cwnd_after_reduction = cubic.congestion_window();
+
+        cubic.on_ack(11, MAX_SEGMENT_SIZE, Duration::from_millis(20));
+
+        assert!(cubic.congestion_window() >= cwnd_after_reduction);
+    }
+}
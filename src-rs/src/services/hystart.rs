@@ -0,0 +1,198 @@
+use std::time::Duration;
+
+/// Divisor applied to per-ack growth while in Conservative Slow Start.
+pub const CSS_GROWTH_DIVISOR: u32 = 4;
+
+/// Number of consecutive Conservative Slow Start rounds before slow start is abandoned.
+const CSS_ROUNDS: u32 = 5;
+
+/// Minimum RTT samples required in a round before it is eligible to trigger CSS.
+const MIN_RTT_SAMPLES_PER_ROUND: u32 = 8;
+
+const MIN_RTT_THRESH: Duration = Duration::from_millis(4);
+const MAX_RTT_THRESH: Duration = Duration::from_millis(16);
+
+/// The slow-start growth mode HyStart++ recommends for the ack just processed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SlowStartMode {
+    /// Ordinary slow start: grow the window by one segment per acked segment.
+    SlowStart,
+    /// Conservative Slow Start: grow the window by `1 / CSS_GROWTH_DIVISOR` of a
+    /// segment per acked segment.
+    ConservativeSlowStart,
+    /// HyStart++ has detected the onset of queuing delay for `CSS_ROUNDS` rounds in a
+    /// row; the controller should set `ssthresh` to the current window and move to
+    /// congestion avoidance.
+    ExitSlowStart
+}
+
+/// HyStart++ (RFC 9406) slow-start exit detection.
+///
+/// Groups acks into rounds — one round is one congestion window's worth of acked
+/// sequences — and watches the per-round minimum RTT for the climb that signals a
+/// link's buffer filling, so slow start can be curtailed before it drives the
+/// link into loss.
+pub struct HyStart {
+    round_end_sequence: u64,
+    last_round_min_rtt: Option<Duration>,
+    current_round_min_rtt: Option<Duration>,
+    rtt_samples_this_round: u32,
+    css_baseline_rtt: Option<Duration>,
+    css_rounds: u32,
+    in_css: bool
+}
+
+impl HyStart {
+    /// Creates a fresh tracker. Call again (or [`HyStart::reset`]) whenever a
+    /// controller re-enters slow start.
+    pub fn new() -> Self {
+        HyStart {
+            round_end_sequence: 0,
+            last_round_min_rtt: None,
+            current_round_min_rtt: None,
+            rtt_samples_this_round: 0,
+            css_baseline_rtt: None,
+            css_rounds: 0,
+            in_css: false
+        }
+    }
+
+    /// Resets all round tracking, e.g. when a controller leaves slow start after a
+    /// congestion event or a HyStart++ exit.
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Marks `sequence` as sent, extending the current round's boundary if it is the
+    /// highest sequence sent so far.
+    pub fn on_packet_sent(&mut self, sequence: u64) {
+        if sequence > self.round_end_sequence {
+            self.round_end_sequence = sequence;
+        }
+    }
+
+    /// Records an ack for `sequence`, observed with round-trip time `rtt`, and returns
+    /// the slow-start growth mode that should be applied for this ack.
+    pub fn on_ack(&mut self, sequence: u64, rtt: Duration) -> SlowStartMode {
+        self.current_round_min_rtt = Some(
+            self.current_round_min_rtt.map_or(rtt, |min| min.min(rtt))
+        );
+        self.rtt_samples_this_round += 1;
+
+        let mode = if self.in_css {
+            SlowStartMode::ConservativeSlowStart
+        } else {
+            SlowStartMode::SlowStart
+        };
+
+        if sequence < self.round_end_sequence {
+            return mode;
+        }
+
+        // This ack completes the round: decide whether RTT has climbed enough to
+        // enter (or continue) Conservative Slow Start.
+        let round_min_rtt = self.current_round_min_rtt.take().unwrap_or(rtt);
+        let samples = self.rtt_samples_this_round;
+        self.rtt_samples_this_round = 0;
+        self.round_end_sequence = sequence;
+
+        if self.in_css {
+            if round_min_rtt < self.css_baseline_rtt.unwrap_or(round_min_rtt) {
+                self.in_css = false;
+                self.css_rounds = 0;
+                self.last_round_min_rtt = Some(round_min_rtt);
+                return SlowStartMode::SlowStart;
+            }
+
+            self.css_rounds += 1;
+            self.last_round_min_rtt = Some(round_min_rtt);
+            if self.css_rounds >= CSS_ROUNDS {
+                return SlowStartMode::ExitSlowStart;
+            }
+            return SlowStartMode::ConservativeSlowStart;
+        }
+
+        if let Some(last_min) = self.last_round_min_rtt {
+            let rtt_thresh = (last_min / 8).clamp(MIN_RTT_THRESH, MAX_RTT_THRESH);
+            if samples >= MIN_RTT_SAMPLES_PER_ROUND && round_min_rtt >= last_min + rtt_thresh {
+                self.in_css = true;
+                self.css_baseline_rtt = Some(round_min_rtt);
+                self.css_rounds = 1;
+                self.last_round_min_rtt = Some(round_min_rtt);
+                return SlowStartMode::ConservativeSlowStart;
+            }
+        }
+
+        self.last_round_min_rtt = Some(round_min_rtt);
+        mode
+    }
+}
+
+impl Default for HyStart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stays_in_slow_start_while_rtt_is_flat() {
+        let mut hystart = HyStart::new();
+        hystart.on_packet_sent(10);
+
+        for seq in 1..=10u64 {
+            let mode = hystart.on_ack(seq, Duration::from_millis(20));
+            assert_eq!(mode, SlowStartMode::SlowStart);
+        }
+    }
+
+    #[test]
+    fn rising_round_rtt_triggers_conservative_slow_start() {
+        let mut hystart = HyStart::new();
+
+        hystart.on_packet_sent(8);
+        for seq in 1..=8u64 {
+            hystart.on_ack(seq, Duration::from_millis(20));
+        }
+
+        hystart.on_packet_sent(16);
+        let mut last_mode = SlowStartMode::SlowStart;
+        for seq in 9..=16u64 {
+            last_mode = hystart.on_ack(seq, Duration::from_millis(60));
+        }
+
+        assert_eq!(last_mode, SlowStartMode::ConservativeSlowStart);
+    }
+
+    #[test]
+    fn rtt_recovery_below_baseline_returns_to_slow_start() {
+        let mut hystart = HyStart::new();
+
+        hystart.on_packet_sent(8);
+        for seq in 1..=8u64 {
+            hystart.on_ack(seq, Duration::from_millis(20));
+        }
+
+        hystart.on_packet_sent(16);
+        let mut last_mode = SlowStartMode::SlowStart;
+        for seq in 9..=16u64 {
+            last_mode = hystart.on_ack(seq, Duration::from_millis(60));
+        }
+        assert_eq!(last_mode, SlowStartMode::ConservativeSlowStart);
+
+        // A single noisy low-RTT sample mid-round must not flip CSS off early;
+        // only the round's minimum at close should matter.
+        hystart.on_packet_sent(20);
+        let mid_round_mode = hystart.on_ack(17, Duration::from_millis(5));
+        assert_eq!(mid_round_mode, SlowStartMode::ConservativeSlowStart);
+
+        for seq in 18..=20u64 {
+            last_mode = hystart.on_ack(seq, Duration::from_millis(20));
+        }
+
+        assert_eq!(last_mode, SlowStartMode::SlowStart);
+    }
+}
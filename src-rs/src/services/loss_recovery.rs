@@ -0,0 +1,197 @@
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+/// Assumed system timer granularity, added to the probe timeout per RFC 6298.
+const GRANULARITY: Duration = Duration::from_millis(1);
+
+/// The RTT estimate assumed before any sample has been observed.
+const INITIAL_RTT: Duration = Duration::from_millis(100);
+
+/// RACK-style reordering window: a sequence sent more than `9/8` of an `srtt`
+/// before a later sequence was acked is presumed lost rather than merely delayed.
+const REORDER_THRESH_NUMERATOR: u32 = 9;
+const REORDER_THRESH_DENOMINATOR: u32 = 8;
+
+/// Tracks per-sequence send timestamps for in-flight reliable data, estimates the
+/// round-trip time (RFC 6298), and derives a probe timeout for declaring a
+/// sequence lost - either because it has aged past the timeout, or because a
+/// later sequence was acked while it was still outstanding (RACK-style
+/// reordering detection).
+pub struct LossRecovery {
+    sent_at: BTreeMap<u64, Instant>,
+    srtt: Duration,
+    rttvar: Duration,
+    has_sample: bool,
+    pto_backoff: u32
+}
+
+impl LossRecovery {
+    /// Creates a tracker with no in-flight sequences and the RFC 6298 initial RTT.
+    pub fn new() -> Self {
+        LossRecovery {
+            sent_at: BTreeMap::new(),
+            srtt: INITIAL_RTT,
+            rttvar: INITIAL_RTT / 2,
+            has_sample: false,
+            pto_backoff: 0
+        }
+    }
+
+    /// Records that `sequence` was sent at `now`.
+    pub fn on_packet_sent(&mut self, sequence: u64, now: Instant) {
+        self.sent_at.insert(sequence, now);
+    }
+
+    /// Processes an `Acknowledge` of `sequence`, observed at `now`. Updates the RTT
+    /// estimate from the newest acked sequence (if it was still tracked) and
+    /// returns any older, still-outstanding sequences that reordering now declares
+    /// lost.
+    pub fn on_ack(&mut self, sequence: u64, now: Instant) -> Vec<u64> {
+        if let Some(sent_at) = self.sent_at.remove(&sequence) {
+            self.on_rtt_sample(now.duration_since(sent_at));
+            self.pto_backoff = 0;
+        }
+
+        let reorder_thresh = self.srtt * REORDER_THRESH_NUMERATOR / REORDER_THRESH_DENOMINATOR;
+        let lost: Vec<u64> = self.sent_at
+            .iter()
+            .filter(|&(&seq, &seq_sent_at)| {
+                seq < sequence && now.duration_since(seq_sent_at) >= reorder_thresh
+            })
+            .map(|(&seq, _)| seq)
+            .collect();
+
+        for seq in &lost {
+            self.sent_at.remove(seq);
+        }
+
+        lost
+    }
+
+    /// Returns the still-outstanding sequences whose probe timeout has elapsed as
+    /// of `now`, and backs off the timeout for the next call.
+    pub fn expired_sequences(&mut self, now: Instant) -> Vec<u64> {
+        let pto = self.pto();
+        let expired: Vec<u64> = self.sent_at
+            .iter()
+            .filter(|&(_, &sent_at)| now.duration_since(sent_at) >= pto)
+            .map(|(&seq, _)| seq)
+            .collect();
+
+        if !expired.is_empty() {
+            self.pto_backoff = self.pto_backoff.saturating_add(1);
+        }
+
+        expired
+    }
+
+    /// Gets the current smoothed round-trip time estimate (`srtt`).
+    pub fn srtt(&self) -> Duration {
+        self.srtt
+    }
+
+    /// Gets the current probe timeout: `srtt + 4*rttvar + granularity`, doubled for
+    /// each consecutive expiration since the last acknowledged progress.
+    pub fn pto(&self) -> Duration {
+        (self.srtt + self.rttvar * 4 + GRANULARITY) * (1u32 << self.pto_backoff.min(16))
+    }
+
+    fn on_rtt_sample(&mut self, sample: Duration) {
+        if !self.has_sample {
+            self.srtt = sample;
+            self.rttvar = sample / 2;
+            self.has_sample = true;
+            return;
+        }
+
+        let deviation = self.srtt.abs_diff(sample);
+        self.rttvar = (self.rttvar * 3 + deviation) / 4;
+        self.srtt = (self.srtt * 7 + sample) / 8;
+    }
+}
+
+impl Default for LossRecovery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_sample_seeds_srtt_directly() {
+        let mut recovery = LossRecovery::new();
+        let sent_at = Instant::now();
+        recovery.on_packet_sent(1, sent_at);
+
+        recovery.on_ack(1, sent_at + Duration::from_millis(40));
+
+        assert_eq!(recovery.srtt(), Duration::from_millis(40));
+    }
+
+    #[test]
+    fn later_samples_smooth_towards_the_new_rtt() {
+        let mut recovery = LossRecovery::new();
+        let start = Instant::now();
+
+        recovery.on_packet_sent(1, start);
+        recovery.on_ack(1, start + Duration::from_millis(40));
+        let srtt_after_first = recovery.srtt();
+
+        recovery.on_packet_sent(2, start);
+        recovery.on_ack(2, start + Duration::from_millis(80));
+
+        assert!(recovery.srtt() > srtt_after_first);
+        assert!(recovery.srtt() < Duration::from_millis(80));
+    }
+
+    #[test]
+    fn acking_a_later_sequence_declares_an_older_one_lost_by_reordering() {
+        let mut recovery = LossRecovery::new();
+        let start = Instant::now();
+
+        // Seed a stable RTT estimate first.
+        recovery.on_packet_sent(1, start);
+        recovery.on_ack(1, start + Duration::from_millis(40));
+
+        // Sequence 2 is sent, then 3 is sent and acked well after 2 should have
+        // arrived - 2 is presumed lost to reordering.
+        recovery.on_packet_sent(2, start + Duration::from_millis(100));
+        recovery.on_packet_sent(3, start + Duration::from_millis(110));
+        let lost = recovery.on_ack(3, start + Duration::from_millis(300));
+
+        assert_eq!(lost, vec![2]);
+    }
+
+    #[test]
+    fn expired_sequences_back_off_the_probe_timeout() {
+        let mut recovery = LossRecovery::new();
+        let start = Instant::now();
+        recovery.on_packet_sent(1, start);
+
+        let pto_before = recovery.pto();
+        let expired = recovery.expired_sequences(start + pto_before);
+
+        assert_eq!(expired, vec![1]);
+        assert!(recovery.pto() > pto_before);
+    }
+
+    #[test]
+    fn a_stale_ack_for_an_untracked_sequence_does_not_reset_the_probe_timeout_backoff() {
+        let mut recovery = LossRecovery::new();
+        let start = Instant::now();
+        recovery.on_packet_sent(1, start);
+
+        let pto_before = recovery.pto();
+        recovery.expired_sequences(start + pto_before);
+        let pto_after_backoff = recovery.pto();
+
+        // Sequence 99 was never sent, so this ack makes no progress and must
+        // not reset the timeout backoff sustained loss just earned.
+        recovery.on_ack(99, start + pto_before);
+
+        assert_eq!(recovery.pto(), pto_after_backoff);
+    }
+}
@@ -0,0 +1,9 @@
+pub mod ack_policy;
+pub mod congestion_control;
+pub mod connection_migration;
+pub mod cubic;
+pub mod hystart;
+pub mod loss_recovery;
+pub mod new_reno;
+pub mod rc4;
+pub mod reset_token;
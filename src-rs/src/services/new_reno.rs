@@ -0,0 +1,146 @@
+use std::time::Duration;
+
+use crate::services::congestion_control::{CongestionControl, MAX_SEGMENT_SIZE};
+use crate::services::hystart::{HyStart, SlowStartMode, CSS_GROWTH_DIVISOR};
+
+/// A [`CongestionControl`] implementation of the standard NewReno algorithm
+/// (RFC 5681 / RFC 6582): additive-increase/multiplicative-decrease, with a
+/// slow-start phase bounded by `ssthresh` and gated by [`HyStart`].
+pub struct NewReno {
+    cwnd: usize,
+    ssthresh: usize,
+    bytes_in_flight: usize,
+    hystart: HyStart
+}
+
+impl NewReno {
+    /// Creates a new controller, starting in slow start with an unbounded `ssthresh`.
+    pub fn new() -> Self {
+        NewReno {
+            cwnd: 2 * MAX_SEGMENT_SIZE,
+            ssthresh: usize::MAX,
+            bytes_in_flight: 0,
+            hystart: HyStart::new()
+        }
+    }
+
+    /// Returns `true` while the controller is still in the slow-start phase.
+    pub fn in_slow_start(&self) -> bool {
+        self.cwnd < self.ssthresh
+    }
+
+    fn enter_congestion_avoidance(&mut self, cwnd: usize) {
+        self.ssthresh = cwnd;
+        self.cwnd = cwnd;
+        self.hystart.reset();
+    }
+}
+
+impl Default for NewReno {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CongestionControl for NewReno {
+    fn on_packet_sent(&mut self, sequence: u64, bytes: usize) {
+        self.bytes_in_flight += bytes;
+
+        if self.in_slow_start() {
+            self.hystart.on_packet_sent(sequence);
+        }
+    }
+
+    fn on_ack(&mut self, sequence: u64, bytes: usize, rtt: Duration) {
+        self.bytes_in_flight = self.bytes_in_flight.saturating_sub(bytes);
+
+        if !self.in_slow_start() {
+            self.cwnd += (MAX_SEGMENT_SIZE * MAX_SEGMENT_SIZE) / self.cwnd;
+            return;
+        }
+
+        match self.hystart.on_ack(sequence, rtt) {
+            SlowStartMode::SlowStart => self.cwnd += MAX_SEGMENT_SIZE,
+            SlowStartMode::ConservativeSlowStart => {
+                self.cwnd += MAX_SEGMENT_SIZE / CSS_GROWTH_DIVISOR as usize;
+            }
+            SlowStartMode::ExitSlowStart => self.enter_congestion_avoidance(self.cwnd)
+        }
+    }
+
+    fn on_congestion_event(&mut self, _lost_sequence: u64) {
+        let cwnd = (self.cwnd / 2).max(2 * MAX_SEGMENT_SIZE);
+        self.enter_congestion_avoidance(cwnd);
+    }
+
+    fn can_send(&self) -> usize {
+        self.cwnd.saturating_sub(self.bytes_in_flight)
+    }
+
+    fn congestion_window(&self) -> usize {
+        self.cwnd
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slow_start_grows_by_one_segment_per_ack() {
+        let mut reno = NewReno::new();
+        let initial = reno.congestion_window();
+
+        reno.on_ack(1, MAX_SEGMENT_SIZE, Duration::from_millis(50));
+
+        assert_eq!(reno.congestion_window(), initial + MAX_SEGMENT_SIZE);
+        assert!(reno.in_slow_start());
+    }
+
+    #[test]
+    fn congestion_event_halves_the_window_and_exits_slow_start() {
+        let mut reno = NewReno::new();
+        for seq in 1..=10u64 {
+            reno.on_ack(seq, MAX_SEGMENT_SIZE, Duration::from_millis(50));
+        }
+        let cwnd_before = reno.congestion_window();
+
+        reno.on_congestion_event(1);
+
+        assert_eq!(reno.congestion_window(), (cwnd_before / 2).max(2 * MAX_SEGMENT_SIZE));
+        assert!(!reno.in_slow_start());
+    }
+
+    #[test]
+    fn can_send_accounts_for_bytes_in_flight() {
+        let mut reno = NewReno::new();
+        let cwnd = reno.congestion_window();
+
+        reno.on_packet_sent(1, MAX_SEGMENT_SIZE);
+
+        assert_eq!(reno.can_send(), cwnd - MAX_SEGMENT_SIZE);
+    }
+
+    #[test]
+    fn rising_rtt_curtails_slow_start_growth() {
+        let mut reno = NewReno::new();
+
+        reno.on_packet_sent(8, MAX_SEGMENT_SIZE);
+        for seq in 1..=8u64 {
+            reno.on_ack(seq, MAX_SEGMENT_SIZE, Duration::from_millis(20));
+        }
+
+        reno.on_packet_sent(16, MAX_SEGMENT_SIZE);
+        for seq in 9..=15u64 {
+            reno.on_ack(seq, MAX_SEGMENT_SIZE, Duration::from_millis(60));
+        }
+        let cwnd_before_last_ack = reno.congestion_window();
+
+        // The round-closing ack observes a round-trip time that has climbed well
+        // past the HyStart++ threshold, so it should grow conservatively rather
+        // than by a full segment.
+        reno.on_ack(16, MAX_SEGMENT_SIZE, Duration::from_millis(60));
+
+        assert!(reno.congestion_window() - cwnd_before_last_ack < MAX_SEGMENT_SIZE);
+    }
+}
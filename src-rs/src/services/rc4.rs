@@ -9,7 +9,7 @@ impl Rc4 {
     pub fn new(key: &[u8]) -> Rc4 {
         const STATE_LENGTH: usize = 256;
 
-        assert!(key.len() >= 1 && key.len() <= STATE_LENGTH);
+        assert!(!key.is_empty() && key.len() <= STATE_LENGTH);
         let mut rc4 = Rc4 {
             index_1: 0,
             index_2: 0,
@@ -29,21 +29,22 @@ impl Rc4 {
             swap_index_1 = (swap_index_1 + 1) % key.len();
         }
 
-        return rc4;
+        rc4
     }
 
-    pub fn next(&mut self) -> u8 {
+    /// Produces the next byte of the RC4 keystream.
+    pub fn next_byte(&mut self) -> u8 {
         self.index_1 = self.index_1.wrapping_add(1);
         self.index_2 = self.index_2.wrapping_add(self.s_1());
         self.state.swap(self.index_1.into(), self.index_2.into());
 
         let index: usize = self.s_1().wrapping_add(self.s_2()).into();
-        return self.state[index];
+        self.state[index]
     }
 
     pub fn transform(&mut self, buffer: &mut [u8]) {
         for i in buffer {
-            *i = *i ^ self.next();
+            *i ^= self.next_byte();
         }
     }
 
@@ -96,7 +97,7 @@ mod tests {
         assert!(buffer.iter().zip(cipher.iter()).all(|(a,b)| a == b))
     }
 
-    unsafe fn get_mutable_string_bytes(value: &mut String) -> &mut [u8] {
+    unsafe fn get_mutable_string_bytes(value: &mut str) -> &mut [u8] {
         value.as_bytes_mut()
     }
 }
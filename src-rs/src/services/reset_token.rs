@@ -0,0 +1,157 @@
+/// Length, in bytes, of a session's stateless reset token.
+pub const RESET_TOKEN_LENGTH: usize = 16;
+
+/// Session-config toggle for whether an `UnknownSender` reply should carry a
+/// stateless reset token for the targeted session id, per [`ResetTokenSecret::unknown_sender_tail`].
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct StatelessResetConfig {
+    /// When `true`, unknown-sender replies carry a reset token tail.
+    pub enabled: bool
+}
+
+/// A node-local secret used to derive every session's 128-bit stateless reset
+/// token (borrowed from QUIC's stateless reset design). Because the token is a
+/// deterministic, keyed function of the session id and a secret that outlives
+/// any individual session, an endpoint that has lost all session state (e.g.
+/// after a restart) can still prove to a peer that it recognizes - and wants to
+/// tear down - a session the peer keeps sending reliable data for.
+#[derive(Clone, Copy)]
+pub struct ResetTokenSecret(u64, u64);
+
+impl ResetTokenSecret {
+    /// Creates a secret from a 128-bit key. This should be generated once per
+    /// node and kept stable across restarts for the stateless-reset guarantee to
+    /// hold.
+    pub fn new(key: [u64; 2]) -> Self {
+        ResetTokenSecret(key[0], key[1])
+    }
+
+    /// Derives the 128-bit stateless reset token for `session_id`, to be echoed
+    /// in the session's `SessionResponse` and recognised later if the session's
+    /// state is ever lost.
+    pub fn derive_token(&self, session_id: u32) -> [u8; RESET_TOKEN_LENGTH] {
+        let low = sip_hash_2_4(self.0, self.1, session_id as u64);
+        let high = sip_hash_2_4(self.1, self.0, session_id as u64 ^ u64::MAX);
+
+        let mut token = [0u8; RESET_TOKEN_LENGTH];
+        token[..8].copy_from_slice(&low.to_be_bytes());
+        token[8..].copy_from_slice(&high.to_be_bytes());
+        token
+    }
+
+    /// Builds the optional reset-token tail to append to an `UnknownSender`
+    /// reply for `session_id`, if `config` has the behavior enabled.
+    pub fn unknown_sender_tail(
+        &self,
+        session_id: u32,
+        config: StatelessResetConfig
+    ) -> Option<[u8; RESET_TOKEN_LENGTH]> {
+        config.enabled.then(|| self.derive_token(session_id))
+    }
+}
+
+/// Checks whether `tail` carries the stateless reset token this node stored for
+/// a session at establishment, in which case the session should be torn down
+/// immediately. Uses a constant-time comparison so a peer probing an endpoint
+/// cannot use response timing to recover a valid token byte-by-byte.
+pub fn is_stateless_reset(tail: &[u8], stored_token: &[u8; RESET_TOKEN_LENGTH]) -> bool {
+    tokens_equal(tail, stored_token)
+}
+
+/// Compares two byte slices in constant time.
+fn tokens_equal(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    a.iter().zip(b.iter()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// A compact, single-block SipHash-2-4 (64-bit output), used to key-derive
+/// reset tokens from a node-local secret without pulling in an external crypto
+/// crate.
+fn sip_hash_2_4(k0: u64, k1: u64, input: u64) -> u64 {
+    let mut v0 = k0 ^ 0x736f_6d65_7073_6575;
+    let mut v1 = k1 ^ 0x646f_7261_6e64_6f6d;
+    let mut v2 = k0 ^ 0x6c79_6765_6e65_7261;
+    let mut v3 = k1 ^ 0x7465_6462_7974_6573;
+
+    macro_rules! sip_round {
+        () => {
+            v0 = v0.wrapping_add(v1);
+            v1 = v1.rotate_left(13);
+            v1 ^= v0;
+            v0 = v0.rotate_left(32);
+            v2 = v2.wrapping_add(v3);
+            v3 = v3.rotate_left(16);
+            v3 ^= v2;
+            v0 = v0.wrapping_add(v3);
+            v3 = v3.rotate_left(21);
+            v3 ^= v0;
+            v2 = v2.wrapping_add(v1);
+            v1 = v1.rotate_left(17);
+            v1 ^= v2;
+            v2 = v2.rotate_left(32);
+        };
+    }
+
+    v3 ^= input;
+    sip_round!();
+    sip_round!();
+    v0 ^= input;
+
+    v2 ^= 0xff;
+    sip_round!();
+    sip_round!();
+    sip_round!();
+    sip_round!();
+
+    v0 ^ v1 ^ v2 ^ v3
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_same_session_id_and_secret_always_derive_the_same_token() {
+        let secret = ResetTokenSecret::new([1, 2]);
+
+        assert_eq!(secret.derive_token(42), secret.derive_token(42));
+    }
+
+    #[test]
+    fn different_session_ids_derive_different_tokens() {
+        let secret = ResetTokenSecret::new([1, 2]);
+
+        assert_ne!(secret.derive_token(42), secret.derive_token(43));
+    }
+
+    #[test]
+    fn different_secrets_derive_different_tokens_for_the_same_session() {
+        let a = ResetTokenSecret::new([1, 2]);
+        let b = ResetTokenSecret::new([3, 4]);
+
+        assert_ne!(a.derive_token(42), b.derive_token(42));
+    }
+
+    #[test]
+    fn unknown_sender_tail_is_only_emitted_when_enabled() {
+        let secret = ResetTokenSecret::new([1, 2]);
+
+        assert_eq!(secret.unknown_sender_tail(42, StatelessResetConfig { enabled: false }), None);
+        assert_eq!(
+            secret.unknown_sender_tail(42, StatelessResetConfig { enabled: true }),
+            Some(secret.derive_token(42))
+        );
+    }
+
+    #[test]
+    fn a_matching_tail_is_recognised_as_a_stateless_reset() {
+        let secret = ResetTokenSecret::new([1, 2]);
+        let stored_token = secret.derive_token(42);
+
+        assert!(is_stateless_reset(&stored_token, &stored_token));
+        assert!(!is_stateless_reset(&secret.derive_token(43), &stored_token));
+    }
+}
@@ -0,0 +1,463 @@
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::time::Instant;
+
+use crate::services::ack_policy::{AckPolicy, AckPolicyConfig};
+use crate::services::congestion_control::{CongestionControl, CongestionControlAlgorithm};
+use crate::services::connection_migration::{ConnectionMigration, MigrationAction};
+use crate::services::loss_recovery::LossRecovery;
+use crate::services::reset_token::{is_stateless_reset, ResetTokenSecret, StatelessResetConfig, RESET_TOKEN_LENGTH};
+use crate::util::data_utils::get_true_incoming_sequence;
+
+/// Session-config parameters that are negotiated or fixed once per session, as
+/// opposed to the moment-to-moment state each service tracks for itself.
+#[derive(Clone, Debug)]
+pub struct SessionConfig {
+    /// Which [`CongestionControl`] algorithm the outgoing reliable-data pump is
+    /// governed by.
+    pub congestion_control: CongestionControlAlgorithm,
+    /// The negotiated `Acknowledge` coalescing policy.
+    pub ack_policy: AckPolicyConfig,
+    /// The maximum number of reliable data packets that may be queued ahead of
+    /// the last acknowledged sequence, used to disambiguate wrapped sequence
+    /// numbers in [`get_true_incoming_sequence`].
+    pub max_queued_reliable_data_packets: i16,
+    /// Whether `UnknownSender` replies for this session should carry a
+    /// stateless reset token.
+    pub stateless_reset: StatelessResetConfig
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        SessionConfig {
+            congestion_control: CongestionControlAlgorithm::default(),
+            ack_policy: AckPolicyConfig::default(),
+            max_queued_reliable_data_packets: 0x1000,
+            stateless_reset: StatelessResetConfig::default()
+        }
+    }
+}
+
+/// A reliable data packet queued for send, awaiting its turn to be pumped onto
+/// the wire once the congestion window allows it.
+struct OutgoingReliableData {
+    sequence: u64,
+    payload: Vec<u8>
+}
+
+/// Ties the previously-isolated congestion control, loss recovery, ack
+/// coalescing, connection migration and stateless reset services together into
+/// the actual reliable-data send/ack/retransmit/receive loop a session drives.
+pub struct Session {
+    session_id: u32,
+    congestion: Box<dyn CongestionControl>,
+    loss_recovery: LossRecovery,
+    ack_policy: AckPolicy,
+    migration: ConnectionMigration,
+    reset_token_secret: ResetTokenSecret,
+    max_queued_reliable_data_packets: i16,
+    stateless_reset: StatelessResetConfig,
+    /// The stateless reset token the peer advertised in its own `SessionResponse`,
+    /// recognised later by [`on_unknown_sender_tail`](Session::on_unknown_sender_tail)
+    /// if the peer ever signals it has lost this session's state. `None` until the
+    /// peer's `SessionResponse` has been processed.
+    peer_reset_token: Option<[u8; RESET_TOKEN_LENGTH]>,
+    outgoing: VecDeque<OutgoingReliableData>,
+    /// Payloads that have been sent and not yet acknowledged, keyed by
+    /// sequence, so a sequence declared lost by
+    /// [`poll_retransmissions`](Session::poll_retransmissions) can actually be
+    /// retransmitted rather than just reported.
+    in_flight: HashMap<u64, Vec<u8>>,
+    next_outgoing_sequence: u64,
+    current_incoming_sequence: u64
+}
+
+impl Session {
+    /// Creates a session for `session_id`, whose peer is initially reachable at
+    /// `remote`, using `config` and keyed off the node-local `reset_token_secret`.
+    /// `peer_ack_policy` is the peer's advertised ack-coalescing preference,
+    /// negotiated against `config.ack_policy` (the more demanding of each field
+    /// wins) to settle the effective policy for this session.
+    pub fn new(
+        session_id: u32,
+        remote: SocketAddr,
+        config: SessionConfig,
+        reset_token_secret: ResetTokenSecret,
+        peer_ack_policy: AckPolicyConfig
+    ) -> Self {
+        let ack_policy = config.ack_policy.negotiate(&peer_ack_policy);
+
+        Session {
+            session_id,
+            congestion: config.congestion_control.build(),
+            loss_recovery: LossRecovery::new(),
+            ack_policy: AckPolicy::new(ack_policy),
+            migration: ConnectionMigration::new(remote),
+            reset_token_secret,
+            max_queued_reliable_data_packets: config.max_queued_reliable_data_packets,
+            stateless_reset: config.stateless_reset,
+            peer_reset_token: None,
+            outgoing: VecDeque::new(),
+            in_flight: HashMap::new(),
+            next_outgoing_sequence: 1,
+            current_incoming_sequence: 0
+        }
+    }
+
+    /// Queues `payload` as the next outgoing reliable data sequence. It will be
+    /// sent once [`pump_outgoing_reliable_data`](Session::pump_outgoing_reliable_data)
+    /// is next called and the congestion window has room for it.
+    pub fn queue_reliable_data(&mut self, payload: Vec<u8>) {
+        let sequence = self.next_outgoing_sequence;
+        self.next_outgoing_sequence += 1;
+        self.outgoing.push_back(OutgoingReliableData { sequence, payload });
+    }
+
+    /// Sends as many queued reliable data packets as `can_send` currently
+    /// allows, recording each against loss recovery and congestion control and
+    /// retaining its payload for a future retransmission. Returns the
+    /// sequence/payload pairs to actually write to the wire.
+    pub fn pump_outgoing_reliable_data(&mut self, now: Instant) -> Vec<(u64, Vec<u8>)> {
+        let mut sent = Vec::new();
+
+        while let Some(next) = self.outgoing.front() {
+            if next.payload.len() > self.congestion.can_send() {
+                break;
+            }
+
+            let packet = self.outgoing.pop_front().unwrap();
+            self.congestion.on_packet_sent(packet.sequence, packet.payload.len());
+            self.loss_recovery.on_packet_sent(packet.sequence, now);
+            self.in_flight.insert(packet.sequence, packet.payload.clone());
+            sent.push((packet.sequence, packet.payload));
+        }
+
+        sent
+    }
+
+    /// Processes an `Acknowledge` of `sequence`, observed at `now`. Feeds the
+    /// round-trip sample and the acknowledged payload's size into loss
+    /// recovery and congestion control, and looks up in `in_flight` the payload
+    /// of any older sequence reordering now declares lost, leaving it there
+    /// until that sequence is itself acknowledged. A sequence not currently in
+    /// flight (e.g. a duplicate ack) feeds nothing into congestion control, but
+    /// still drives reordering-based loss detection for older sequences.
+    /// Returns the sequence/payload pairs to retransmit, re-arming loss
+    /// recovery's timer for each.
+    pub fn on_acknowledge(&mut self, sequence: u64, now: Instant) -> Vec<(u64, Vec<u8>)> {
+        if let Some(payload) = self.in_flight.remove(&sequence) {
+            let rtt = self.loss_recovery.srtt();
+            self.congestion.on_ack(sequence, payload.len(), rtt);
+        }
+
+        let lost = self.loss_recovery.on_ack(sequence, now);
+        let mut retransmissions = Vec::with_capacity(lost.len());
+
+        for lost_sequence in lost {
+            self.congestion.on_congestion_event(lost_sequence);
+
+            if let Some(payload) = self.in_flight.get(&lost_sequence) {
+                self.loss_recovery.on_packet_sent(lost_sequence, now);
+                retransmissions.push((lost_sequence, payload.clone()));
+            }
+        }
+
+        retransmissions
+    }
+
+    /// Reports any outstanding sequences whose probe timeout has elapsed as of
+    /// `now` as lost to congestion control, and returns the sequence/payload
+    /// pairs to retransmit, re-arming loss recovery's timer for each.
+    pub fn poll_retransmissions(&mut self, now: Instant) -> Vec<(u64, Vec<u8>)> {
+        let expired = self.loss_recovery.expired_sequences(now);
+        let mut retransmissions = Vec::with_capacity(expired.len());
+
+        for sequence in expired {
+            self.congestion.on_congestion_event(sequence);
+
+            if let Some(payload) = self.in_flight.get(&sequence) {
+                self.loss_recovery.on_packet_sent(sequence, now);
+                retransmissions.push((sequence, payload.clone()));
+            }
+        }
+
+        retransmissions
+    }
+
+    /// Records that a reliable data packet carrying `packet_sequence` arrived
+    /// at `now`, updating the true incoming sequence and the ack-coalescing
+    /// policy. Returns `true` if an `Acknowledge` should be sent immediately.
+    pub fn on_reliable_data_received(&mut self, packet_sequence: u16, now: Instant) -> bool {
+        let true_sequence = get_true_incoming_sequence(
+            packet_sequence,
+            self.current_incoming_sequence,
+            self.max_queued_reliable_data_packets
+        );
+
+        if true_sequence == self.current_incoming_sequence + 1 {
+            self.current_incoming_sequence = true_sequence;
+            self.ack_policy.on_in_order_packet(now)
+        } else {
+            self.ack_policy.on_out_of_order_packet();
+            true
+        }
+    }
+
+    /// Returns `true` if the delayed-ack timer has fired and an `Acknowledge`
+    /// should be sent even though `ack_threshold` has not been reached.
+    pub fn poll_delayed_ack(&self, now: Instant) -> bool {
+        self.ack_policy.is_delay_expired(now)
+    }
+
+    /// Clears pending-ack state after an `Acknowledge` has been sent.
+    pub fn on_acknowledge_sent(&mut self) {
+        self.ack_policy.on_ack_sent();
+    }
+
+    /// Observes an inbound packet from `from`, driving the connection
+    /// migration challenge-response if it arrives from a new address.
+    pub fn on_packet_received_from(
+        &mut self,
+        from: SocketAddr,
+        next_token: impl FnOnce() -> [u8; crate::services::connection_migration::MIGRATION_TOKEN_LENGTH]
+    ) -> MigrationAction {
+        self.migration.on_packet_received(from, next_token)
+    }
+
+    /// Validates an echoed `RemapConnection` response, rebinding the session's
+    /// address on success.
+    pub fn on_remap_response(&mut self, from: SocketAddr, echoed_token: &[u8]) -> bool {
+        self.migration.on_remap_response(from, echoed_token)
+    }
+
+    /// Gets the peer address this session currently believes is validated.
+    pub fn remote(&self) -> SocketAddr {
+        self.migration.current()
+    }
+
+    /// Derives this session's stateless reset token, to be echoed in its
+    /// `SessionResponse`.
+    pub fn reset_token(&self) -> [u8; RESET_TOKEN_LENGTH] {
+        self.reset_token_secret.derive_token(self.session_id)
+    }
+
+    /// Builds the optional reset-token tail for an `UnknownSender` reply
+    /// targeting this session id, if stateless reset is enabled.
+    pub fn unknown_sender_tail(&self) -> Option<[u8; RESET_TOKEN_LENGTH]> {
+        self.reset_token_secret.unknown_sender_tail(self.session_id, self.stateless_reset)
+    }
+
+    /// Records the stateless reset token the peer advertised in its own
+    /// `SessionResponse`, so a later `UnknownSender` reply echoing it back can be
+    /// recognised by [`on_unknown_sender_tail`](Session::on_unknown_sender_tail).
+    pub fn set_peer_reset_token(&mut self, token: [u8; RESET_TOKEN_LENGTH]) {
+        self.peer_reset_token = Some(token);
+    }
+
+    /// Checks the tail of an inbound `UnknownSender` reply against the
+    /// stateless reset token the peer advertised at session setup. A match is
+    /// an authenticated signal that the peer has lost this session's state, and
+    /// the caller should drop the session immediately rather than keep
+    /// retransmitting to it.
+    pub fn on_unknown_sender_tail(&self, tail: &[u8]) -> bool {
+        self.peer_reset_token
+            .as_ref()
+            .is_some_and(|token| is_stateless_reset(tail, token))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(port: u16) -> SocketAddr {
+        format!("127.0.0.1:{port}").parse().unwrap()
+    }
+
+    #[test]
+    fn queued_reliable_data_is_pumped_while_the_congestion_window_allows_it() {
+        let mut session = Session::new(
+            1,
+            addr(1000),
+            SessionConfig::default(),
+            ResetTokenSecret::new([1, 2]),
+            AckPolicyConfig::default()
+        );
+        session.queue_reliable_data(vec![0u8; 16]);
+        session.queue_reliable_data(vec![1u8; 16]);
+
+        let sent = session.pump_outgoing_reliable_data(Instant::now());
+
+        assert_eq!(sent, vec![(1, vec![0u8; 16]), (2, vec![1u8; 16])]);
+    }
+
+    #[test]
+    fn acknowledging_a_sent_sequence_reports_no_loss() {
+        let mut session = Session::new(
+            1,
+            addr(1000),
+            SessionConfig::default(),
+            ResetTokenSecret::new([1, 2]),
+            AckPolicyConfig::default()
+        );
+        session.queue_reliable_data(vec![0u8; 16]);
+        let now = Instant::now();
+        session.pump_outgoing_reliable_data(now);
+
+        let lost = session.on_acknowledge(1, now + std::time::Duration::from_millis(20));
+
+        assert!(lost.is_empty());
+    }
+
+    #[test]
+    fn acking_a_later_sequence_hands_back_an_older_reordered_one_for_retransmission() {
+        let mut session = Session::new(
+            1,
+            addr(1000),
+            SessionConfig::default(),
+            ResetTokenSecret::new([1, 2]),
+            AckPolicyConfig::default()
+        );
+        let start = Instant::now();
+
+        // Seed a stable RTT estimate first.
+        session.queue_reliable_data(vec![0u8; 16]);
+        session.pump_outgoing_reliable_data(start);
+        session.on_acknowledge(1, start + std::time::Duration::from_millis(40));
+
+        // Sequence 2 is sent, then 3 is sent and acked well after 2 should have
+        // arrived - 2 is presumed lost to reordering and must come back for
+        // retransmission rather than just being reported to congestion control.
+        session.queue_reliable_data(vec![1u8; 16]);
+        session.pump_outgoing_reliable_data(start + std::time::Duration::from_millis(100));
+        session.queue_reliable_data(vec![2u8; 16]);
+        session.pump_outgoing_reliable_data(start + std::time::Duration::from_millis(110));
+
+        let retransmissions = session.on_acknowledge(3, start + std::time::Duration::from_millis(300));
+
+        assert_eq!(retransmissions, vec![(2, vec![1u8; 16])]);
+    }
+
+    #[test]
+    fn an_expired_sequence_is_handed_back_with_its_original_payload_for_retransmission() {
+        let mut session = Session::new(
+            1,
+            addr(1000),
+            SessionConfig::default(),
+            ResetTokenSecret::new([1, 2]),
+            AckPolicyConfig::default()
+        );
+        session.queue_reliable_data(vec![5u8; 16]);
+        let now = Instant::now();
+        session.pump_outgoing_reliable_data(now);
+        let pto = session.loss_recovery.pto();
+
+        let retransmissions = session.poll_retransmissions(now + pto);
+
+        assert_eq!(retransmissions, vec![(1, vec![5u8; 16])]);
+    }
+
+    #[test]
+    fn reaching_the_ack_threshold_requests_an_immediate_acknowledge() {
+        let mut session = Session::new(
+            1,
+            addr(1000),
+            SessionConfig {
+                ack_policy: AckPolicyConfig { ack_threshold: 2, max_ack_delay: std::time::Duration::from_millis(25) },
+                ..SessionConfig::default()
+            },
+            ResetTokenSecret::new([1, 2]),
+            AckPolicyConfig::default()
+        );
+        let now = Instant::now();
+
+        assert!(!session.on_reliable_data_received(1, now));
+        assert!(session.on_reliable_data_received(2, now));
+    }
+
+    #[test]
+    fn session_setup_negotiates_the_peers_advertised_ack_policy() {
+        let session = Session::new(
+            1,
+            addr(1000),
+            SessionConfig {
+                ack_policy: AckPolicyConfig { ack_threshold: 4, max_ack_delay: std::time::Duration::from_millis(10) },
+                ..SessionConfig::default()
+            },
+            ResetTokenSecret::new([1, 2]),
+            AckPolicyConfig { ack_threshold: 2, max_ack_delay: std::time::Duration::from_millis(50) }
+        );
+
+        assert_eq!(
+            session.ack_policy.config(),
+            AckPolicyConfig { ack_threshold: 2, max_ack_delay: std::time::Duration::from_millis(10) }
+        );
+    }
+
+    #[test]
+    fn the_first_sequence_of_a_session_is_treated_as_in_order() {
+        let mut session = Session::new(
+            1,
+            addr(1000),
+            SessionConfig::default(),
+            ResetTokenSecret::new([1, 2]),
+            AckPolicyConfig::default()
+        );
+
+        session.queue_reliable_data(vec![0u8; 16]);
+        let sent = session.pump_outgoing_reliable_data(Instant::now());
+        assert_eq!(sent, vec![(1, vec![0u8; 16])]);
+
+        assert!(!session.on_reliable_data_received(1, Instant::now()));
+    }
+
+    #[test]
+    fn a_migration_challenge_is_only_trusted_once_echoed_back() {
+        let mut session = Session::new(
+            1,
+            addr(1000),
+            SessionConfig::default(),
+            ResetTokenSecret::new([1, 2]),
+            AckPolicyConfig::default()
+        );
+        let token = [7; crate::services::connection_migration::MIGRATION_TOKEN_LENGTH];
+
+        let action = session.on_packet_received_from(addr(2000), || token);
+
+        assert_eq!(action, MigrationAction::ChallengeCandidate { candidate: addr(2000), token });
+        assert_eq!(session.remote(), addr(1000));
+
+        assert!(session.on_remap_response(addr(2000), &token));
+        assert_eq!(session.remote(), addr(2000));
+    }
+
+    #[test]
+    fn a_stateless_reset_echoing_the_peers_stored_token_signals_the_session_should_drop() {
+        let mut session = Session::new(
+            1,
+            addr(1000),
+            SessionConfig::default(),
+            ResetTokenSecret::new([1, 2]),
+            AckPolicyConfig::default()
+        );
+        let peer_secret = ResetTokenSecret::new([3, 4]);
+        let peer_token = peer_secret.derive_token(7);
+        session.set_peer_reset_token(peer_token);
+
+        assert!(!session.on_unknown_sender_tail(&peer_secret.derive_token(8)));
+        assert!(session.on_unknown_sender_tail(&peer_token));
+    }
+
+    #[test]
+    fn no_stored_peer_token_never_recognises_a_stateless_reset() {
+        let session = Session::new(
+            1,
+            addr(1000),
+            SessionConfig::default(),
+            ResetTokenSecret::new([1, 2]),
+            AckPolicyConfig::default()
+        );
+
+        assert!(!session.on_unknown_sender_tail(&[0u8; RESET_TOKEN_LENGTH]));
+    }
+}
@@ -1,3 +1,5 @@
+use num_derive::{FromPrimitive, ToPrimitive};
+
 /// Enumerates the packet OP codes used in the SOE protocol.
 #[repr(u16)]
 #[derive(Copy, Clone, Debug, Eq, FromPrimitive, PartialEq, ToPrimitive)]
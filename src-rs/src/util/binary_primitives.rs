@@ -1,23 +1,50 @@
+use crate::util::octets::{BufferTooShortError, Octets, OctetsMut};
+
 pub mod read {
-    pub fn read_u16_be(buffer: &[u8]) -> u16 {
-        let mut value: u16 = (buffer[0] as u16) << 8;
-        value |= buffer[1] as u16;
-        
-        value
+    use super::*;
+
+    pub fn read_u16_be(buffer: &[u8]) -> Result<u16, BufferTooShortError> {
+        Octets::with_slice(buffer).get_u16()
     }
 
-    pub fn read_u32_be(buffer: &[u8]) -> u32 {
-        let mut value: u32 = (buffer[0] as u32) << 24;
-        value |= (buffer[1] as u32) << 16;
-        value |= (buffer[2] as u32) << 8;
-        value |= buffer[3] as u32;
-        
-        value
+    pub fn read_u32_be(buffer: &[u8]) -> Result<u32, BufferTooShortError> {
+        Octets::with_slice(buffer).get_u32()
     }
 }
 
 pub mod write {
-    pub fn write_u16_be(buffer: &mut [u8], value: u16) {
-        buffer[0] = (value >> 8) as u8
+    use super::*;
+
+    pub fn write_u16_be(buffer: &mut [u8], value: u16) -> Result<(), BufferTooShortError> {
+        OctetsMut::with_slice(buffer).put_u16(value)
+    }
+
+    pub fn write_u32_be(buffer: &mut [u8], value: u32) -> Result<(), BufferTooShortError> {
+        OctetsMut::with_slice(buffer).put_u32(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_u16_be() {
+        let mut buffer: [u8; 2] = [0; 2];
+        write::write_u16_be(&mut buffer, 0x1234).unwrap();
+        assert_eq!(read::read_u16_be(&buffer), Ok(0x1234));
+    }
+
+    #[test]
+    fn round_trips_u32_be() {
+        let mut buffer: [u8; 4] = [0; 4];
+        write::write_u32_be(&mut buffer, 0xDEAD_BEEF).unwrap();
+        assert_eq!(read::read_u32_be(&buffer), Ok(0xDEAD_BEEF));
+    }
+
+    #[test]
+    fn rejects_truncated_buffers() {
+        let buffer: [u8; 1] = [0xFF];
+        assert_eq!(read::read_u16_be(&buffer), Err(BufferTooShortError));
     }
 }
@@ -1,4 +1,11 @@
-use std::mem::size_of;
+//! Variable-length and multi-packet framing helpers for reliable-data wire
+//! (de)serialization, built on the bounds-checked [`crate::util::octets`]
+//! cursors. These are meant to back `ReliableDataFragment`/`MultiPacket`
+//! (de)serialization once that packet layer is written; [`crate::session`]
+//! does not yet encode or decode packets itself, so nothing calls these
+//! outside their own tests yet.
+
+use crate::util::octets::{BufferTooShortError, Octets, OctetsMut};
 
 pub const MULTI_DATA_INDICATOR: [u8; 2] = [0x00, 0x19];
 
@@ -11,15 +18,15 @@ pub fn get_true_incoming_sequence(
 ) -> u64 {
     // Note; this method makes the assumption that the amount of queued reliable data
     // can never be more than slightly less than the max value of a ushort
-    
+
     // Zero-out the lower two bytes of our last known sequence and
     // and insert the packet sequence in that space
     let mut sequence: u64 = packet_sequence as u64 | (current_sequence & 0xFFFFFFFFFFFF0000);
-    
+
     // If the sequence we obtain is smaller than our possible window, we must have wrapped
     // forward to the next 'packet sequence' block, and hence need to increment the true
     // sequence by an entire block
-    if sequence < current_sequence - max_queued_reliable_data_packets as u64 {
+    if sequence < current_sequence.saturating_sub(max_queued_reliable_data_packets as u64) {
         sequence += 0xFFFF + 1
     }
     // If the sequence we obtain is larger than our possible window, we must have wrapped back
@@ -28,7 +35,7 @@ pub fn get_true_incoming_sequence(
     else if sequence > current_sequence + max_queued_reliable_data_packets as u64 {
         sequence -= 0xFFFF + 1
     }
-    
+
     sequence
 }
 
@@ -39,55 +46,88 @@ pub fn has_multi_data_indicator(buffer: &[u8]) -> bool {
         && buffer[0..2] == MULTI_DATA_INDICATOR
 }
 
-/// Writes the `MULTI_DATA_INDICATOR` to the given buffer, and increments the offset appropriately.
+/// Writes the `MULTI_DATA_INDICATOR` to the given cursor.
 #[inline]
-pub fn write_multi_data_indicator(buffer: &mut [u8], offset: &mut usize) {
-    let end_offset = *offset + MULTI_DATA_INDICATOR.len();
-    buffer[*offset..end_offset].copy_from_slice(&MULTI_DATA_INDICATOR);
-    *offset += 2;
+pub fn write_multi_data_indicator(octets: &mut OctetsMut) -> Result<(), BufferTooShortError> {
+    octets.put_bytes(&MULTI_DATA_INDICATOR)
 }
 
-/// Reads a variable length value from a buffer.
-pub fn read_variable_length(buffer: &[u8], offset: &mut usize) -> u32 {
-    let mut value: u32 = 0;
+/// Reads a variable length value from a cursor.
+pub fn read_variable_length(octets: &mut Octets) -> Result<u32, BufferTooShortError> {
+    let marker = octets.get_u8()?;
 
-    if buffer[*offset] < u8::MAX
-    {
-        value = buffer[*offset] as u32;
-        *offset += 1;
-    }
-    else if buffer[*offset + 1] == u8::MAX && buffer[*offset + 2] == u8::MAX
-    {
-        value = u32::from_be_bytes(buffer[(*offset + 3)..].split_at(size_of::<u32>()).try_into().unwrap());
-        value |= (buffer[*offset + 3] as u32) << 24;
-        value |= (buffer[*offset + 4] as u32) << 16;
-        value |= (buffer[*offset + 5] as u32) << 8;
-        value |= buffer[*offset + 6] as u32;
-        *offset += 7;
-    }
-    else
-    {
-        value |= (buffer[*offset + 1] as u32) << 8;
-        value |= buffer[*offset + 2] as u32;
-        *offset += 3;
+    if marker < u8::MAX {
+        return Ok(marker as u32);
     }
 
-    value
+    let lookahead = octets.peek_bytes(2)?;
+    if lookahead[0] == u8::MAX && lookahead[1] == u8::MAX {
+        octets.get_bytes(2)?;
+        octets.get_u32()
+    } else {
+        octets.get_u16().map(|value| value as u32)
+    }
 }
 
 /// Gets the amount of space in a buffer that a variable length value will consume.
 pub fn get_variable_length_size(length: u32) -> usize {
     if length < 0xFF {
-        size_of::<u8>()
-    }
-    else if length < 0xFFFF {
-        size_of::<u16>() + 1
+        1
+    } else if length < 0xFFFF {
+        3
+    } else {
+        7
     }
-    else {
-        size_of::<u32>() + 3
+}
+
+/// Writes a variable length value to a cursor.
+pub fn write_variable_length(octets: &mut OctetsMut, length: u32) -> Result<(), BufferTooShortError> {
+    if length < 0xFF {
+        octets.put_u8(length as u8)
+    } else if length < 0xFFFF {
+        octets.put_u8(u8::MAX)?;
+        octets.put_u16(length as u16)
+    } else {
+        octets.put_bytes(&[u8::MAX, u8::MAX, u8::MAX])?;
+        octets.put_u32(length)
     }
 }
 
-pub fn write_variable_length(buffer: &mut [u8], length: u32, offset: &mut usize) {
-    
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(length: u32) {
+        let mut buffer = [0u8; 16];
+        {
+            let mut writer = OctetsMut::with_slice(&mut buffer);
+            write_variable_length(&mut writer, length).unwrap();
+        }
+
+        let mut reader = Octets::with_slice(&buffer);
+        assert_eq!(read_variable_length(&mut reader), Ok(length));
+        assert_eq!(reader.offset(), get_variable_length_size(length));
+    }
+
+    #[test]
+    fn round_trips_single_byte_length() {
+        round_trip(0x12);
+    }
+
+    #[test]
+    fn round_trips_u16_length() {
+        round_trip(0x1234);
+    }
+
+    #[test]
+    fn round_trips_u32_length() {
+        round_trip(0x1234_5678);
+    }
+
+    #[test]
+    fn rejects_truncated_buffer() {
+        let buffer: [u8; 1] = [u8::MAX];
+        let mut reader = Octets::with_slice(&buffer);
+        assert_eq!(read_variable_length(&mut reader), Err(BufferTooShortError));
+    }
 }
@@ -0,0 +1,3 @@
+pub mod binary_primitives;
+pub mod data_utils;
+pub mod octets;
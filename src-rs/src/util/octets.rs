@@ -0,0 +1,244 @@
+use std::error::Error;
+use std::fmt;
+
+/// Indicates that a buffer did not contain enough remaining bytes to complete
+/// a read or write operation.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct BufferTooShortError;
+
+impl fmt::Display for BufferTooShortError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "buffer does not contain enough remaining bytes")
+    }
+}
+
+impl Error for BufferTooShortError {}
+
+/// A bounds-checked, read-only cursor over a byte slice.
+///
+/// Every accessor validates that enough bytes remain before touching the
+/// underlying buffer, so a truncated packet yields a [`BufferTooShortError`]
+/// rather than a panic.
+pub struct Octets<'a> {
+    buffer: &'a [u8],
+    offset: usize
+}
+
+impl<'a> Octets<'a> {
+    /// Wraps `buffer` in a cursor starting at offset zero.
+    pub fn with_slice(buffer: &'a [u8]) -> Self {
+        Octets { buffer, offset: 0 }
+    }
+
+    /// Gets the current cursor offset.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Gets the total length of the wrapped buffer.
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Gets the number of bytes remaining after the cursor.
+    pub fn cap(&self) -> usize {
+        self.buffer.len() - self.offset
+    }
+
+    /// Returns `true` if there are no bytes remaining after the cursor.
+    pub fn is_empty(&self) -> bool {
+        self.cap() == 0
+    }
+
+    /// Reads an unsigned 8-bit integer and advances the cursor.
+    pub fn get_u8(&mut self) -> Result<u8, BufferTooShortError> {
+        let value = self.peek_u8()?;
+        self.offset += 1;
+        Ok(value)
+    }
+
+    /// Reads an unsigned 8-bit integer without advancing the cursor.
+    pub fn peek_u8(&self) -> Result<u8, BufferTooShortError> {
+        if self.cap() < 1 {
+            return Err(BufferTooShortError);
+        }
+
+        Ok(self.buffer[self.offset])
+    }
+
+    /// Reads a big-endian unsigned 16-bit integer and advances the cursor.
+    pub fn get_u16(&mut self) -> Result<u16, BufferTooShortError> {
+        let bytes = self.get_bytes(2)?;
+        Ok(u16::from_be_bytes(bytes.try_into().unwrap()))
+    }
+
+    /// Reads a big-endian unsigned 32-bit integer and advances the cursor.
+    pub fn get_u32(&mut self) -> Result<u32, BufferTooShortError> {
+        let bytes = self.get_bytes(4)?;
+        Ok(u32::from_be_bytes(bytes.try_into().unwrap()))
+    }
+
+    /// Reads a big-endian unsigned 64-bit integer and advances the cursor.
+    pub fn get_u64(&mut self) -> Result<u64, BufferTooShortError> {
+        let bytes = self.get_bytes(8)?;
+        Ok(u64::from_be_bytes(bytes.try_into().unwrap()))
+    }
+
+    /// Reads `len` raw bytes and advances the cursor.
+    pub fn get_bytes(&mut self, len: usize) -> Result<&'a [u8], BufferTooShortError> {
+        let bytes = self.peek_bytes(len)?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    /// Reads `len` raw bytes without advancing the cursor.
+    pub fn peek_bytes(&self, len: usize) -> Result<&'a [u8], BufferTooShortError> {
+        if self.cap() < len {
+            return Err(BufferTooShortError);
+        }
+
+        Ok(&self.buffer[self.offset..self.offset + len])
+    }
+
+    /// Returns the remaining, unread portion of the buffer.
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.buffer[self.offset..]
+    }
+}
+
+/// A bounds-checked, mutable cursor over a byte slice.
+///
+/// Mirrors [`Octets`] but for writing; every accessor validates that enough
+/// space remains before touching the underlying buffer.
+pub struct OctetsMut<'a> {
+    buffer: &'a mut [u8],
+    offset: usize
+}
+
+impl<'a> OctetsMut<'a> {
+    /// Wraps `buffer` in a cursor starting at offset zero.
+    pub fn with_slice(buffer: &'a mut [u8]) -> Self {
+        OctetsMut { buffer, offset: 0 }
+    }
+
+    /// Gets the current cursor offset.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Gets the total length of the wrapped buffer.
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Gets the number of bytes remaining after the cursor.
+    pub fn cap(&self) -> usize {
+        self.buffer.len() - self.offset
+    }
+
+    /// Returns `true` if there are no bytes remaining after the cursor.
+    pub fn is_empty(&self) -> bool {
+        self.cap() == 0
+    }
+
+    /// Reads an unsigned 8-bit integer and advances the cursor.
+    pub fn get_u8(&mut self) -> Result<u8, BufferTooShortError> {
+        let value = self.as_octets().get_u8();
+        if value.is_ok() {
+            self.offset += 1;
+        }
+        value
+    }
+
+    /// Reads a big-endian unsigned 16-bit integer and advances the cursor.
+    pub fn get_u16(&mut self) -> Result<u16, BufferTooShortError> {
+        let value = self.as_octets().get_u16();
+        if value.is_ok() {
+            self.offset += 2;
+        }
+        value
+    }
+
+    /// Reads a big-endian unsigned 32-bit integer and advances the cursor.
+    pub fn get_u32(&mut self) -> Result<u32, BufferTooShortError> {
+        let value = self.as_octets().get_u32();
+        if value.is_ok() {
+            self.offset += 4;
+        }
+        value
+    }
+
+    /// Writes an unsigned 8-bit integer and advances the cursor.
+    pub fn put_u8(&mut self, value: u8) -> Result<(), BufferTooShortError> {
+        self.put_bytes(&[value])
+    }
+
+    /// Writes a big-endian unsigned 16-bit integer and advances the cursor.
+    pub fn put_u16(&mut self, value: u16) -> Result<(), BufferTooShortError> {
+        self.put_bytes(&value.to_be_bytes())
+    }
+
+    /// Writes a big-endian unsigned 32-bit integer and advances the cursor.
+    pub fn put_u32(&mut self, value: u32) -> Result<(), BufferTooShortError> {
+        self.put_bytes(&value.to_be_bytes())
+    }
+
+    /// Writes a big-endian unsigned 64-bit integer and advances the cursor.
+    pub fn put_u64(&mut self, value: u64) -> Result<(), BufferTooShortError> {
+        self.put_bytes(&value.to_be_bytes())
+    }
+
+    /// Writes raw bytes and advances the cursor.
+    pub fn put_bytes(&mut self, value: &[u8]) -> Result<(), BufferTooShortError> {
+        if self.cap() < value.len() {
+            return Err(BufferTooShortError);
+        }
+
+        let end_offset = self.offset + value.len();
+        self.buffer[self.offset..end_offset].copy_from_slice(value);
+        self.offset = end_offset;
+
+        Ok(())
+    }
+
+    /// Returns the remaining, unwritten portion of the buffer.
+    pub fn remaining(&self) -> &[u8] {
+        &self.buffer[self.offset..]
+    }
+
+    /// Returns the remaining, unwritten portion of the buffer.
+    pub fn remaining_mut(&mut self) -> &mut [u8] {
+        &mut self.buffer[self.offset..]
+    }
+
+    fn as_octets(&self) -> Octets<'_> {
+        Octets { buffer: self.buffer, offset: self.offset }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_advances_offset_and_rejects_short_buffers() {
+        let buffer: [u8; 4] = [0x00, 0x01, 0x02, 0x03];
+        let mut octets = Octets::with_slice(&buffer);
+
+        assert_eq!(octets.get_u8(), Ok(0x00));
+        assert_eq!(octets.get_u16(), Ok(0x0102));
+        assert_eq!(octets.get_u8(), Ok(0x03));
+        assert_eq!(octets.get_u8(), Err(BufferTooShortError));
+    }
+
+    #[test]
+    fn put_advances_offset_and_rejects_short_buffers() {
+        let mut buffer: [u8; 3] = [0; 3];
+        let mut octets = OctetsMut::with_slice(&mut buffer);
+
+        assert_eq!(octets.put_u8(0xAB), Ok(()));
+        assert_eq!(octets.put_u16(0x1234), Ok(()));
+        assert_eq!(octets.put_u8(0x00), Err(BufferTooShortError));
+        assert_eq!(buffer, [0xAB, 0x12, 0x34]);
+    }
+}